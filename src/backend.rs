@@ -0,0 +1,403 @@
+use std::fs;
+use std::io::{self, BufRead};
+
+/// Raw memory accounting read from the cgroup hierarchy, independent of
+/// whether the host is mounted in v1 or v2 mode.
+#[derive(Default, Clone)]
+pub struct MemoryReadings {
+    pub inactive_anon: u64,
+    pub active_anon: u64,
+    pub inactive_file: u64,
+    pub active_file: u64,
+    pub current_memory_usage: u64,
+    pub current_swap_usage: u64,
+    pub memory_max: u64,
+    pub swap_max: u64,
+    pub events: MemoryEvents,
+}
+
+/// Counters from `memory.events`: how many times reclaim pressure has crossed
+/// each threshold since the cgroup was created. v1 has no equivalent file, so
+/// the v1 backend always reports zeros here.
+#[derive(Default, Clone)]
+pub struct MemoryEvents {
+    pub low: u64,
+    pub high: u64,
+    pub max: u64,
+    pub oom: u64,
+    pub oom_kill: u64,
+}
+
+/// Abstracts over the cgroup v1/v2 file layouts so the regulation logic in
+/// `CgroupsReclaimManager` doesn't need to know which hierarchy it's on.
+pub trait MemoryBackend {
+    fn get_statistics(&self) -> Result<MemoryReadings, String>;
+    fn set_max_memory(&self, max_memory: u64) -> Result<(), String>;
+    fn reclaim_memory(&self, value: u64) -> Result<(), String>;
+    /// PSI `full avg10` for this cgroup, i.e. the percentage of the last 10s
+    /// during which every task was stalled on memory. `None` if the kernel or
+    /// hierarchy doesn't expose `memory.pressure`.
+    fn pressure_full_avg10(&self) -> Option<f64>;
+    /// Soft throttle limit (`memory.high` on v2). Pass `u64::MAX` to lift it.
+    /// Not supported on v1, which has no throttling equivalent.
+    fn set_high(&self, high: u64) -> Result<(), String>;
+    /// Best-effort reclaim protection (`memory.low` on v2, `memory.soft_limit_in_bytes` on v1).
+    fn set_low(&self, low: u64) -> Result<(), String>;
+    /// Whether `set_high` is backed by a real kernel knob on this hierarchy.
+    /// `false` on v1, which has no `memory.high` equivalent and always fails
+    /// `set_high`; callers should fall back to a hard reclaim instead.
+    fn supports_soft_throttle(&self) -> bool {
+        true
+    }
+}
+
+/// cgroup v2 files accept the literal string `"max"` in place of a numeric
+/// value to mean "no limit"; `u64::MAX` is our in-process stand-in for that.
+fn format_limit(value: u64) -> String {
+    if value == u64::MAX {
+        "max".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parses the PSI `full avg10=X.XX` field out of a `memory.pressure` file.
+pub(crate) fn parse_psi_full_avg10(path: &str) -> Option<f64> {
+    let contents = fs::read_to_string(path).ok()?;
+    let full_line = contents.lines().find(|line| line.starts_with("full "))?;
+
+    full_line
+        .split_whitespace()
+        .find_map(|field| field.strip_prefix("avg10="))
+        .and_then(|value| value.parse::<f64>().ok())
+}
+
+/// Parses the flat `key value` format shared by `memory.stat`, `memory.events`
+/// and similar cgroup files into a map.
+pub(crate) fn parse_flat_keyed(path: &str) -> Result<std::collections::HashMap<String, u64>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let reader = io::BufReader::new(file);
+    let mut map = std::collections::HashMap::new();
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("Failed to read line: {}", e))?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() == 2 {
+            if let Ok(value) = parts[1].parse::<u64>() {
+                map.insert(parts[0].to_string(), value);
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+fn read_u64_file(path: &str) -> u64 {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| match s.trim() {
+            "max" => Some(u64::MAX),
+            value => value.parse::<u64>().ok(),
+        })
+        .unwrap_or(0)
+}
+
+const MEMORY_MAX: &str = "memory.max";
+const MEMORY_CURRENT: &str = "memory.current";
+const MEMORY_STAT: &str = "memory.stat";
+const MEMORY_RECLAIM: &str = "memory.reclaim";
+const SWAP_MAX: &str = "memory.swap.max";
+const SWAP_CURRENT: &str = "memory.swap.current";
+
+const MEMORY_EVENTS: &str = "memory.events";
+const MEMORY_PRESSURE: &str = "memory.pressure";
+const MEMORY_HIGH: &str = "memory.high";
+const MEMORY_LOW: &str = "memory.low";
+
+pub struct CgroupV2Backend {
+    memory_max_path: String,
+    memory_current_path: String,
+    memory_stat_path: String,
+    memory_reclaim_path: String,
+    memory_events_path: String,
+    memory_pressure_path: String,
+    memory_high_path: String,
+    memory_low_path: String,
+    swap_max_path: String,
+    swap_current_path: String,
+}
+
+impl CgroupV2Backend {
+    pub fn new(cgroup_path: &str) -> Self {
+        CgroupV2Backend {
+            memory_max_path: format!("{}/{}", cgroup_path, MEMORY_MAX),
+            memory_current_path: format!("{}/{}", cgroup_path, MEMORY_CURRENT),
+            memory_stat_path: format!("{}/{}", cgroup_path, MEMORY_STAT),
+            memory_reclaim_path: format!("{}/{}", cgroup_path, MEMORY_RECLAIM),
+            memory_events_path: format!("{}/{}", cgroup_path, MEMORY_EVENTS),
+            memory_pressure_path: format!("{}/{}", cgroup_path, MEMORY_PRESSURE),
+            memory_high_path: format!("{}/{}", cgroup_path, MEMORY_HIGH),
+            memory_low_path: format!("{}/{}", cgroup_path, MEMORY_LOW),
+            swap_max_path: format!("{}/{}", cgroup_path, SWAP_MAX),
+            swap_current_path: format!("{}/{}", cgroup_path, SWAP_CURRENT),
+        }
+    }
+}
+
+impl MemoryBackend for CgroupV2Backend {
+    fn get_statistics(&self) -> Result<MemoryReadings, String> {
+        let stat = parse_flat_keyed(&self.memory_stat_path)?;
+        let events = parse_flat_keyed(&self.memory_events_path).unwrap_or_default();
+
+        Ok(MemoryReadings {
+            inactive_anon: *stat.get("inactive_anon").unwrap_or(&0),
+            active_anon: *stat.get("active_anon").unwrap_or(&0),
+            inactive_file: *stat.get("inactive_file").unwrap_or(&0),
+            active_file: *stat.get("active_file").unwrap_or(&0),
+            current_memory_usage: read_u64_file(&self.memory_current_path),
+            current_swap_usage: read_u64_file(&self.swap_current_path),
+            memory_max: read_u64_file(&self.memory_max_path),
+            swap_max: read_u64_file(&self.swap_max_path),
+            events: MemoryEvents {
+                low: *events.get("low").unwrap_or(&0),
+                high: *events.get("high").unwrap_or(&0),
+                max: *events.get("max").unwrap_or(&0),
+                oom: *events.get("oom").unwrap_or(&0),
+                oom_kill: *events.get("oom_kill").unwrap_or(&0),
+            },
+        })
+    }
+
+    fn set_max_memory(&self, max_memory: u64) -> Result<(), String> {
+        fs::write(&self.memory_max_path, max_memory.to_string())
+            .map_err(|e| format!("Failed to set memory.max: {}", e))
+    }
+
+    fn reclaim_memory(&self, value: u64) -> Result<(), String> {
+        fs::write(&self.memory_reclaim_path, value.to_string())
+            .map_err(|e| format!("Failed to reclaim memory: {}", e))
+    }
+
+    fn pressure_full_avg10(&self) -> Option<f64> {
+        parse_psi_full_avg10(&self.memory_pressure_path)
+    }
+
+    fn set_high(&self, high: u64) -> Result<(), String> {
+        fs::write(&self.memory_high_path, format_limit(high))
+            .map_err(|e| format!("Failed to set memory.high: {}", e))
+    }
+
+    fn set_low(&self, low: u64) -> Result<(), String> {
+        fs::write(&self.memory_low_path, format_limit(low))
+            .map_err(|e| format!("Failed to set memory.low: {}", e))
+    }
+}
+
+const MEMORY_LIMIT_IN_BYTES: &str = "memory.limit_in_bytes";
+const MEMORY_USAGE_IN_BYTES: &str = "memory.usage_in_bytes";
+const MEMSW_LIMIT_IN_BYTES: &str = "memory.memsw.limit_in_bytes";
+const MEMSW_USAGE_IN_BYTES: &str = "memory.memsw.usage_in_bytes";
+const MEMORY_STAT_V1: &str = "memory.stat";
+const MEMORY_SOFT_LIMIT_IN_BYTES: &str = "memory.soft_limit_in_bytes";
+
+pub struct CgroupV1Backend {
+    memory_limit_path: String,
+    memory_usage_path: String,
+    memory_stat_path: String,
+    memsw_limit_path: String,
+    memsw_usage_path: String,
+    memory_pressure_path: String,
+    memory_soft_limit_path: String,
+}
+
+impl CgroupV1Backend {
+    pub fn new(cgroup_path: &str) -> Self {
+        CgroupV1Backend {
+            memory_limit_path: format!("{}/{}", cgroup_path, MEMORY_LIMIT_IN_BYTES),
+            memory_usage_path: format!("{}/{}", cgroup_path, MEMORY_USAGE_IN_BYTES),
+            memory_stat_path: format!("{}/{}", cgroup_path, MEMORY_STAT_V1),
+            memsw_limit_path: format!("{}/{}", cgroup_path, MEMSW_LIMIT_IN_BYTES),
+            memsw_usage_path: format!("{}/{}", cgroup_path, MEMSW_USAGE_IN_BYTES),
+            memory_pressure_path: format!("{}/{}", cgroup_path, MEMORY_PRESSURE),
+            memory_soft_limit_path: format!("{}/{}", cgroup_path, MEMORY_SOFT_LIMIT_IN_BYTES),
+        }
+    }
+
+    /// v1 has no `memory.reclaim`; we force kernel reclaim by temporarily
+    /// lowering `memory.limit_in_bytes` toward current usage and restoring it.
+    /// The restore always runs, even if the squeeze itself failed (e.g. the
+    /// kernel refused with EBUSY) — leaving the cgroup pinned at a too-low
+    /// limit would be worse than the reclaim we were trying to perform.
+    fn emulate_reclaim(&self, value: u64) -> Result<(), String> {
+        let current_limit = read_u64_file(&self.memory_limit_path);
+        let current_usage = read_u64_file(&self.memory_usage_path);
+        let squeeze_target = current_usage.saturating_sub(value);
+
+        let squeeze_result = fs::write(&self.memory_limit_path, squeeze_target.to_string())
+            .map_err(|e| format!("Failed to squeeze memory.limit_in_bytes: {}", e));
+
+        let restore_result = fs::write(&self.memory_limit_path, current_limit.to_string())
+            .map_err(|e| format!("Failed to restore memory.limit_in_bytes: {}", e));
+
+        squeeze_result?;
+        restore_result
+    }
+}
+
+impl MemoryBackend for CgroupV1Backend {
+    fn get_statistics(&self) -> Result<MemoryReadings, String> {
+        let stat = parse_flat_keyed(&self.memory_stat_path)?;
+
+        Ok(MemoryReadings {
+            inactive_anon: *stat.get("inactive_anon").unwrap_or(&0),
+            active_anon: *stat.get("active_anon").unwrap_or(&0),
+            inactive_file: *stat.get("inactive_file").unwrap_or(&0),
+            active_file: *stat.get("active_file").unwrap_or(&0),
+            current_memory_usage: read_u64_file(&self.memory_usage_path),
+            current_swap_usage: read_u64_file(&self.memsw_usage_path)
+                .saturating_sub(read_u64_file(&self.memory_usage_path)),
+            memory_max: read_u64_file(&self.memory_limit_path),
+            swap_max: read_u64_file(&self.memsw_limit_path),
+            events: MemoryEvents::default(), // v1 has no memory.events equivalent
+        })
+    }
+
+    fn set_max_memory(&self, max_memory: u64) -> Result<(), String> {
+        fs::write(&self.memory_limit_path, max_memory.to_string())
+            .map_err(|e| format!("Failed to set memory.limit_in_bytes: {}", e))
+    }
+
+    fn reclaim_memory(&self, value: u64) -> Result<(), String> {
+        self.emulate_reclaim(value)
+    }
+
+    fn pressure_full_avg10(&self) -> Option<f64> {
+        parse_psi_full_avg10(&self.memory_pressure_path)
+    }
+
+    fn set_high(&self, _high: u64) -> Result<(), String> {
+        Err("memory.high has no v1 equivalent; use set_max_memory to throttle instead".to_string())
+    }
+
+    fn set_low(&self, low: u64) -> Result<(), String> {
+        fs::write(&self.memory_soft_limit_path, low.to_string())
+            .map_err(|e| format!("Failed to set memory.soft_limit_in_bytes: {}", e))
+    }
+
+    fn supports_soft_throttle(&self) -> bool {
+        false
+    }
+}
+
+/// Probes whether `cgroup_path` is mounted in unified (v2) mode by checking
+/// for `cgroup.controllers`, which only exists under the v2 hierarchy.
+pub fn detect_backend(cgroup_path: &str) -> Box<dyn MemoryBackend> {
+    let unified_marker = format!("{}/cgroup.controllers", cgroup_path);
+
+    if std::path::Path::new(&unified_marker).exists() {
+        Box::new(CgroupV2Backend::new(cgroup_path))
+    } else {
+        Box::new(CgroupV1Backend::new(cgroup_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Builds a fresh scratch directory under the OS temp dir, unique per
+    /// test run (there's no `tempfile` dependency in this tree), removed on drop.
+    struct ScratchDir {
+        path: std::path::PathBuf,
+    }
+
+    impl ScratchDir {
+        fn new(label: &str) -> Self {
+            static COUNTER: AtomicU64 = AtomicU64::new(0);
+            let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+            let path = std::env::temp_dir().join(format!(
+                "cgroups-proactive-reclaim-test-{}-{}-{}",
+                std::process::id(),
+                label,
+                n
+            ));
+            fs::create_dir_all(&path).expect("failed to create scratch cgroup dir");
+            ScratchDir { path }
+        }
+
+        fn path_str(&self) -> String {
+            self.path.to_str().unwrap().to_string()
+        }
+
+        fn write(&self, name: &str, contents: &str) {
+            fs::write(self.path.join(name), contents).expect("failed to write fixture file");
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn detect_backend_picks_v2_when_cgroup_controllers_present() {
+        let dir = ScratchDir::new("detect-v2");
+        dir.write("cgroup.controllers", "cpu io memory\n");
+        dir.write("memory.stat", "inactive_anon 1\nactive_anon 2\ninactive_file 3\nactive_file 4\n");
+        dir.write("memory.current", "1000\n");
+        dir.write("memory.max", "2000\n");
+        dir.write("memory.swap.current", "0\n");
+        dir.write("memory.swap.max", "max\n");
+
+        let backend = detect_backend(&dir.path_str());
+        let stats = backend.get_statistics().expect("get_statistics should succeed");
+
+        assert_eq!(stats.current_memory_usage, 1000);
+        assert_eq!(stats.memory_max, 2000);
+        assert_eq!(stats.inactive_anon, 1);
+        assert_eq!(stats.active_file, 4);
+    }
+
+    #[test]
+    fn detect_backend_picks_v1_when_cgroup_controllers_absent() {
+        let dir = ScratchDir::new("detect-v1");
+        dir.write("memory.stat", "inactive_anon 5\nactive_anon 6\ninactive_file 7\nactive_file 8\n");
+        dir.write("memory.usage_in_bytes", "500\n");
+        dir.write("memory.limit_in_bytes", "1500\n");
+        dir.write("memory.memsw.usage_in_bytes", "600\n");
+        dir.write("memory.memsw.limit_in_bytes", "2000\n");
+
+        let backend = detect_backend(&dir.path_str());
+        let stats = backend.get_statistics().expect("get_statistics should succeed");
+
+        assert_eq!(stats.current_memory_usage, 500);
+        assert_eq!(stats.current_swap_usage, 100); // memsw usage minus memory usage
+        assert_eq!(stats.memory_max, 1500);
+        assert_eq!(stats.inactive_anon, 5);
+
+        // v1 has no memory.high; set_high must report that rather than silently no-op
+        assert!(backend.set_high(100).is_err());
+    }
+
+    #[test]
+    fn cgroup_v2_backend_reads_events_and_psi() {
+        let dir = ScratchDir::new("v2-events-psi");
+        dir.write("cgroup.controllers", "cpu io memory\n");
+        dir.write("memory.stat", "inactive_anon 0\nactive_anon 0\ninactive_file 0\nactive_file 0\n");
+        dir.write("memory.current", "0\n");
+        dir.write("memory.max", "max\n");
+        dir.write("memory.swap.current", "0\n");
+        dir.write("memory.swap.max", "max\n");
+        dir.write("memory.events", "low 1\nhigh 2\nmax 3\noom 4\noom_kill 5\n");
+        dir.write("memory.pressure", "some avg10=1.00 avg60=1.00 avg300=1.00 total=0\nfull avg10=12.34 avg60=1.00 avg300=1.00 total=0\n");
+
+        let backend = CgroupV2Backend::new(&dir.path_str());
+        let stats = backend.get_statistics().expect("get_statistics should succeed");
+
+        assert_eq!(stats.events.low, 1);
+        assert_eq!(stats.events.oom_kill, 5);
+        assert_eq!(backend.pressure_full_avg10(), Some(12.34));
+    }
+}
@@ -0,0 +1,145 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use chrono::Local;
+use csv::Writer;
+
+/// One sample of reclaim telemetry, fuller than just the eight original
+/// columns: usage, swap, the anon/file breakdown, `memory.events`, and PSI.
+#[derive(serde::Serialize, Default, Clone)]
+pub struct LogEntry {
+    pub domain: String,
+    pub timestamp: u64,
+    pub current_memory_usage: u64,
+    pub current_swap_usage: u64,
+    pub memory_max: u64,
+    pub active_anon: u64,
+    pub inactive_anon: u64,
+    pub swap_max: u64,
+    pub active_file: u64,
+    pub inactive_file: u64,
+    pub events_low: u64,
+    pub events_high: u64,
+    pub events_max: u64,
+    pub events_oom: u64,
+    pub events_oom_kill: u64,
+    pub psi_full_avg10: f64, // PSI `full avg10`, i.e. % of the last 10s every task stalled on memory
+}
+
+/// A destination for reclaim telemetry. `CgroupsReclaimManager` can hold
+/// several at once so operators can ship to more than one place per run.
+pub trait LogSink {
+    fn write(&mut self, entry: &LogEntry) -> Result<(), String>;
+}
+
+/// The original behavior: one timestamped CSV file per run.
+pub struct CsvLogSink {
+    writer: Writer<File>,
+}
+
+impl CsvLogSink {
+    pub fn new(domain: &str) -> Result<Self, String> {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let path = format!("cgroup_{}_{}.csv", domain, timestamp);
+        let file = File::create(&path).map_err(|e| format!("Failed to create CSV file: {}", e))?;
+
+        Ok(CsvLogSink {
+            writer: Writer::from_writer(file),
+        })
+    }
+}
+
+impl LogSink for CsvLogSink {
+    fn write(&mut self, entry: &LogEntry) -> Result<(), String> {
+        self.writer
+            .serialize(entry)
+            .map_err(|e| format!("Failed to write to CSV: {}", e))?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush CSV: {}", e))
+    }
+}
+
+/// Newline-delimited JSON, one object per sample, for log shippers that
+/// tail a file rather than scrape CSV.
+pub struct JsonLogSink {
+    writer: BufWriter<File>,
+}
+
+impl JsonLogSink {
+    pub fn new(domain: &str) -> Result<Self, String> {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let path = format!("cgroup_{}_{}.ndjson", domain, timestamp);
+        let file = File::create(&path).map_err(|e| format!("Failed to create NDJSON file: {}", e))?;
+
+        Ok(JsonLogSink {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl LogSink for JsonLogSink {
+    fn write(&mut self, entry: &LogEntry) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, entry)
+            .map_err(|e| format!("Failed to write NDJSON entry: {}", e))?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| format!("Failed to write NDJSON newline: {}", e))?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush NDJSON: {}", e))
+    }
+}
+
+/// InfluxDB line-protocol output, keyed by `domain` as a tag so a single
+/// measurement file (or socket, if swapped for a `TcpStream`) can carry
+/// telemetry for multiple cgroups.
+pub struct InfluxLineLogSink {
+    writer: BufWriter<File>,
+}
+
+impl InfluxLineLogSink {
+    const MEASUREMENT: &'static str = "cgroup_reclaim";
+
+    pub fn new(domain: &str) -> Result<Self, String> {
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let path = format!("cgroup_{}_{}.line", domain, timestamp);
+        let file = File::create(&path).map_err(|e| format!("Failed to create line-protocol file: {}", e))?;
+
+        Ok(InfluxLineLogSink {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl LogSink for InfluxLineLogSink {
+    fn write(&mut self, entry: &LogEntry) -> Result<(), String> {
+        let line = format!(
+            "{},domain={} current_memory_usage={}i,current_swap_usage={}i,memory_max={}i,swap_max={}i,active_anon={}i,inactive_anon={}i,active_file={}i,inactive_file={}i,events_low={}i,events_high={}i,events_max={}i,events_oom={}i,events_oom_kill={}i,psi_full_avg10={} {}\n",
+            Self::MEASUREMENT,
+            entry.domain,
+            entry.current_memory_usage,
+            entry.current_swap_usage,
+            entry.memory_max,
+            entry.swap_max,
+            entry.active_anon,
+            entry.inactive_anon,
+            entry.active_file,
+            entry.inactive_file,
+            entry.events_low,
+            entry.events_high,
+            entry.events_max,
+            entry.events_oom,
+            entry.events_oom_kill,
+            entry.psi_full_avg10,
+            entry.timestamp,
+        );
+
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to write line-protocol entry: {}", e))?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("Failed to flush line-protocol file: {}", e))
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::{CgroupsReclaimManager, MemoryStat, ReclaimStrategy};
+
+/// Runtime tuning knobs for a `CgroupWatcher`, replacing the compile-time
+/// constants `regulate` otherwise falls back to.
+#[derive(Clone)]
+pub struct WatcherConfig {
+    pub poll_interval: Duration,
+    pub history_len: usize,
+    pub stddev_threshold: f64,
+    pub max_reclaim: u64,
+    pub safety: u64,
+    pub strategy: ReclaimStrategy,
+}
+
+/// Latest sample plus a running min/max/avg of memory usage over the last
+/// `history_len` samples, broadcast to anyone subscribed to the watcher.
+#[derive(Clone, Default)]
+pub struct StatsSummary {
+    pub latest: MemoryStat,
+    pub min_usage: u64,
+    pub max_usage: u64,
+    pub avg_usage: f64,
+}
+
+/// Drives `CgroupsReclaimManager::regulate` on a fixed cadence and publishes
+/// the resulting statistics on a `tokio::sync::watch` channel, so a caller no
+/// longer has to poll `regulate` manually or recompile to change tuning.
+pub struct CgroupWatcher {
+    manager: CgroupsReclaimManager,
+    config: WatcherConfig,
+    usage_history: VecDeque<u64>,
+    tx: watch::Sender<StatsSummary>,
+}
+
+impl CgroupWatcher {
+    pub fn new(
+        mut manager: CgroupsReclaimManager,
+        config: WatcherConfig,
+    ) -> (Self, watch::Receiver<StatsSummary>) {
+        manager.set_stddev_threshold(config.stddev_threshold);
+        manager.set_max_reclaim(config.max_reclaim);
+        manager.set_strategy(config.strategy);
+
+        let (tx, rx) = watch::channel(StatsSummary::default());
+
+        (
+            CgroupWatcher {
+                manager,
+                usage_history: VecDeque::with_capacity(config.history_len),
+                config,
+                tx,
+            },
+            rx,
+        )
+    }
+
+    fn record_usage(&mut self, current_memory_usage: u64) -> (u64, u64, f64) {
+        if self.usage_history.len() >= self.config.history_len {
+            self.usage_history.pop_front();
+        }
+        self.usage_history.push_back(current_memory_usage);
+
+        let min_usage = *self.usage_history.iter().min().unwrap_or(&current_memory_usage);
+        let max_usage = *self.usage_history.iter().max().unwrap_or(&current_memory_usage);
+        let avg_usage =
+            self.usage_history.iter().sum::<u64>() as f64 / self.usage_history.len() as f64;
+
+        (min_usage, max_usage, avg_usage)
+    }
+
+    /// Samples statistics, runs the regulation decision, and publishes the
+    /// result every `poll_interval` until the task is dropped or aborted.
+    pub async fn run(mut self, free_memory: u64) {
+        let mut ticker = tokio::time::interval(self.config.poll_interval);
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.manager.regulate(free_memory, self.config.safety) {
+                println!("regulate() failed: {}", e);
+                continue;
+            }
+
+            let latest = match self.manager.stats() {
+                Ok(stats) => stats,
+                Err(e) => {
+                    println!("Failed to read stats after regulate(): {}", e);
+                    continue;
+                }
+            };
+
+            let (min_usage, max_usage, avg_usage) = self.record_usage(latest.current_memory_usage);
+
+            let summary = StatsSummary {
+                latest,
+                min_usage,
+                max_usage,
+                avg_usage,
+            };
+
+            if self.tx.send(summary).is_err() {
+                // No receivers left; keep regulating but stop bothering to report.
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+/// A fixed-capacity ring buffer of samples with O(1) incremental mean/variance
+/// via Welford's online algorithm, periodically recomputed from scratch to
+/// bound floating-point drift from repeated eviction.
+pub(crate) struct SlidingWindow {
+    capacity: usize,
+    recompute_interval: u32,
+    buffer: VecDeque<f64>,
+    count: u64,
+    mean: f64,
+    m2: f64,
+    evictions: u32,
+}
+
+impl SlidingWindow {
+    pub fn new(capacity: usize, recompute_interval: u32) -> Self {
+        SlidingWindow {
+            capacity,
+            recompute_interval,
+            buffer: VecDeque::with_capacity(capacity),
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            evictions: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Adds a new sample, evicting the oldest one first if the window is
+    /// already at capacity.
+    pub fn push(&mut self, x: f64) {
+        if self.buffer.len() >= self.capacity {
+            if let Some(x_old) = self.buffer.pop_front() {
+                if self.count > 1 {
+                    let delta = x_old - self.mean;
+                    self.mean -= delta / (self.count - 1) as f64;
+                    self.m2 -= delta * (x_old - self.mean);
+                    self.count -= 1;
+                } else {
+                    self.count = 0;
+                    self.mean = 0.0;
+                    self.m2 = 0.0;
+                }
+
+                self.evictions += 1;
+                if self.evictions >= self.recompute_interval {
+                    self.recompute();
+                    self.evictions = 0;
+                }
+            }
+        }
+
+        self.buffer.push_back(x);
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    /// Recomputes `mean`/`m2` from scratch over the current buffer contents.
+    fn recompute(&mut self) {
+        let mut count = 0u64;
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+
+        for &x in &self.buffer {
+            count += 1;
+            let delta = x - mean;
+            mean += delta / count as f64;
+            m2 += delta * (x - mean);
+        }
+
+        self.count = count;
+        self.mean = mean;
+        self.m2 = m2;
+    }
+
+    /// O(1) stddev over the current window.
+    pub fn stddev(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        (self.m2 / self.count as f64).sqrt()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.count = 0;
+        self.mean = 0.0;
+        self.m2 = 0.0;
+        self.evictions = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch_stddev(values: &[f64]) -> f64 {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        variance.sqrt()
+    }
+
+    #[test]
+    fn incremental_stddev_matches_batch_recomputation_across_eviction() {
+        let capacity = 5;
+        let mut window = SlidingWindow::new(capacity, 1000);
+        let samples = [10.0, 12.0, 9.0, 15.0, 11.0, 20.0, 8.0, 13.0, 17.0, 6.0];
+
+        for (i, &x) in samples.iter().enumerate() {
+            window.push(x);
+
+            let start = if i + 1 > capacity { i + 1 - capacity } else { 0 };
+            let expected = batch_stddev(&samples[start..=i]);
+
+            assert!(
+                (window.stddev() - expected).abs() < 1e-9,
+                "sample {}: incremental stddev {} != batch stddev {}",
+                i,
+                window.stddev(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn recompute_keeps_drift_in_check() {
+        let capacity = 4;
+        let mut window = SlidingWindow::new(capacity, 3); // force a recompute after 3 evictions
+
+        for i in 0..50 {
+            window.push((i % 7) as f64);
+        }
+
+        let tail: Vec<f64> = (46..50).map(|i| (i % 7) as f64).collect();
+        let expected = batch_stddev(&tail);
+
+        assert!((window.stddev() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clear_resets_to_empty() {
+        let mut window = SlidingWindow::new(3, 1000);
+        window.push(1.0);
+        window.push(2.0);
+        window.clear();
+
+        assert_eq!(window.len(), 0);
+        assert_eq!(window.stddev(), 0.0);
+    }
+}